@@ -4,7 +4,7 @@
 mod tendersecure {
 
     use ink::prelude::string::String;
-    use ink::env::hash::Keccak256;
+    use ink::env::hash::{Blake2x256, Keccak256};
     use ink::storage::{Mapping};
     use ink::prelude::vec::Vec;
 
@@ -12,8 +12,37 @@ mod tendersecure {
     pub struct Tendersecure {
         owner: AccountId,
         submit_proposal_phase_started: bool,
+        reveal_phase_started: bool,
+        /// Identifies the currently open round. Incremented each time a new
+        /// round of bidding starts, so that a closed round's bidder state is
+        /// logically cleared without having to sweep every entry.
+        round_id: u32,
+        /// Whether `round_id` has been closed, either settled (by
+        /// [`Self::pick_bidder`], [`Self::select_lowest_bidder`] or
+        /// [`Self::select_highest_score`]) or cancelled by [`Self::cancel_round`].
+        round_closed: bool,
         bidders: Vec<AccountId>,
-        bidder_proposals: Mapping<AccountId, String>,
+        bidder_commitments: Mapping<(u32, AccountId), [u8; 32]>,
+        bidder_proposals: Mapping<(u32, AccountId), String>,
+        /// Each bidder's deposit for a given round, reclaimable via [`Self::withdraw`].
+        deposits: Mapping<(u32, AccountId), Balance>,
+        /// The bid amount (or score) revealed alongside each bidder's proposal.
+        bid_amounts: Mapping<(u32, AccountId), Balance>,
+        /// The winner recorded for a closed round.
+        winners: Mapping<u32, AccountId>,
+        /// The lowest `bid_amount` revealed so far this round and who
+        /// revealed it, updated incrementally in [`Self::reveal`] so that
+        /// [`Self::select_lowest_bidder`] never has to iterate `bidders`.
+        lowest_bid: Mapping<u32, (AccountId, Balance)>,
+        /// The highest `bid_amount` (score) revealed so far this round and
+        /// who revealed it, updated incrementally in [`Self::reveal`] so
+        /// that [`Self::select_highest_score`] never has to iterate
+        /// `bidders`.
+        highest_score: Mapping<u32, (AccountId, Balance)>,
+        /// The amount the owner has posted for the currently open round,
+        /// held in escrow until it is paid out to the winner or the round
+        /// is cancelled.
+        tender_amount: Balance,
     }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -29,6 +58,39 @@ mod tendersecure {
         BidderAlreadySubmittedProposal,
         /// Returned if there are no entries.
         NoEntries,
+        /// Returned if the revealed proposal does not match the stored commitment.
+        CommitmentMismatch,
+        /// Returned if the caller has no withdrawable deposit.
+        NothingToWithdraw,
+        /// Returned if a withdrawal is attempted before the round is decided.
+        RoundStillOpen,
+        /// Returned if a signed commitment's signature doesn't recover to the
+        /// claimed bidder.
+        InvalidSignature,
+        /// Returned if a signed commitment's deadline has passed.
+        SignatureExpired,
+        /// Returned if a winner-selection message is called while reveals
+        /// are still open.
+        RevealPhaseOpen,
+        /// Returned if `reveal` is called before the reveal phase has started.
+        RevealNotStarted,
+        /// Returned if a round-settling message (`pick_bidder`,
+        /// `select_lowest_bidder`, `select_highest_score` or
+        /// `cancel_round`) is called after the round has already been
+        /// decided.
+        RoundAlreadyClosed,
+    }
+
+    /// The criterion a round was settled by, recorded on [`Won`] for auditability.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(::scale_info::TypeInfo))]
+    pub enum SelectionCriterion {
+        /// The owner picked the winner manually via [`Tendersecure::pick_bidder`].
+        Manual,
+        /// The winner had the lowest revealed `bid_amount`.
+        LowestBid,
+        /// The winner had the highest revealed `bid_amount` (score).
+        HighestScore,
     }
 
     #[ink(event)]
@@ -43,6 +105,14 @@ mod tendersecure {
         winner: AccountId,
         /// The winning amount.
         amount: Balance,
+        /// How the winner was chosen.
+        criterion: SelectionCriterion,
+    }
+
+    #[ink(event)]
+    pub struct RoundCancelled {
+        /// The round that was cancelled.
+        round_id: u32,
     }
 
     /// Type alias for the contract's result type.
@@ -55,8 +125,18 @@ mod tendersecure {
             Self {
                 owner: Self::env().caller(),
                 submit_proposal_phase_started: false,
+                reveal_phase_started: false,
+                round_id: 0,
+                round_closed: false,
                 bidders: Vec::new(),
+                bidder_commitments: Mapping::default(),
                 bidder_proposals: Mapping::default(),
+                deposits: Mapping::default(),
+                bid_amounts: Mapping::default(),
+                winners: Mapping::default(),
+                lowest_bid: Mapping::default(),
+                highest_score: Mapping::default(),
+                tender_amount: 0,
             }
         }
 
@@ -68,7 +148,7 @@ mod tendersecure {
 
         #[ink(message)]
         pub fn get_tender_amount(&self) -> Balance {
-            self.env().balance()
+            self.tender_amount
         }
 
         #[ink(message)]
@@ -76,13 +156,23 @@ mod tendersecure {
             self.submit_proposal_phase_started
         }
 
+        #[ink(message)]
+        pub fn can_reveal(&self) -> bool {
+            self.reveal_phase_started
+        }
+
+        /// Adds to the tender amount held in escrow for the currently open round.
         #[ink(message, payable)]
-        pub fn submit_tender_amount(&self) -> Result<Balance> {
+        pub fn submit_tender_amount(&mut self) -> Result<Balance> {
             if self.env().caller() != self.owner {
                 return Err(Error::CallerNotOwner);
             }
 
-            Ok(Self::env().balance())
+            self.tender_amount = self
+                .tender_amount
+                .saturating_add(self.env().transferred_value());
+
+            Ok(self.tender_amount)
         }
 
         /// Returns the list of bidders
@@ -94,18 +184,143 @@ mod tendersecure {
         /// Retrieve the balance of the account.
         #[ink(message)]
         pub fn get_proposal_for_bidder(&self, caller: AccountId) -> Option<String> {
-            self.bidder_proposals.get(&caller)
+            self.bidder_proposals.get((self.round_id, caller))
         }
 
+        /// Retrieve the revealed bid amount (or score) of the account.
+        #[ink(message)]
+        pub fn get_bid_amount_for_bidder(&self, caller: AccountId) -> Option<Balance> {
+            self.bid_amounts.get((self.round_id, caller))
+        }
+
+        /// Commits to a sealed proposal, as the `keccak256` hash of the SCALE-encoded
+        /// tuple `(url, bid_amount, salt)` (the `url` bytes carry their compact length
+        /// prefix), during the submission phase. One commitment per caller per round.
         #[ink(message, payable)]
-        pub fn enter(&mut self, url:String) -> Result<()> {
+        pub fn commit(&mut self, commitment: [u8; 32]) -> Result<()> {
             if !self.submit_proposal_phase_started {
                 return Err(Error::BiddingNotStarted);
             }
+            if self.reveal_phase_started {
+                return Err(Error::RevealPhaseOpen);
+            }
             let caller = self.env().caller();
 
+            if self.bidder_commitments.contains((self.round_id, caller)) {
+                return Err(Error::BidderAlreadySubmittedProposal);
+            }
+
             self.bidders.push(caller);
-            self.bidder_proposals.insert(caller, &url);
+            self.bidder_commitments
+                .insert((self.round_id, caller), &commitment);
+            self.deposits
+                .insert((self.round_id, caller), &self.env().transferred_value());
+
+            Ok(())
+        }
+
+        /// Commits on behalf of `bidder`, authorized by an ECDSA `signature` over
+        /// `(round_id, bidder, commitment, deadline)`, so a relayer can pay the
+        /// submission fee for a bidder who only signs off-chain.
+        #[ink(message, payable)]
+        pub fn commit_signed(
+            &mut self,
+            bidder: AccountId,
+            commitment: [u8; 32],
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if !self.submit_proposal_phase_started {
+                return Err(Error::BiddingNotStarted);
+            }
+            if self.reveal_phase_started {
+                return Err(Error::RevealPhaseOpen);
+            }
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::SignatureExpired);
+            }
+
+            let mut message_hash = [0u8; 32];
+            self.env().hash_encoded::<Keccak256, _>(
+                &(self.round_id, bidder, commitment, deadline),
+                &mut message_hash,
+            );
+
+            let mut pub_key = [0u8; 33];
+            self.env()
+                .ecdsa_recover(&signature, &message_hash, &mut pub_key)
+                .map_err(|_| Error::InvalidSignature)?;
+
+            let mut recovered = [0u8; 32];
+            self.env().hash_bytes::<Blake2x256>(&pub_key, &mut recovered);
+
+            if AccountId::from(recovered) != bidder {
+                return Err(Error::InvalidSignature);
+            }
+
+            if self.bidder_commitments.contains((self.round_id, bidder)) {
+                return Err(Error::BidderAlreadySubmittedProposal);
+            }
+
+            self.bidders.push(bidder);
+            self.bidder_commitments
+                .insert((self.round_id, bidder), &commitment);
+            self.deposits
+                .insert((self.round_id, bidder), &self.env().transferred_value());
+
+            Ok(())
+        }
+
+        /// Reveals a previously committed proposal, verifying it against the
+        /// stored commitment from [`Self::commit`].
+        #[ink(message)]
+        pub fn reveal(&mut self, url: String, bid_amount: Balance, salt: [u8; 32]) -> Result<()> {
+            if !self.reveal_phase_started {
+                return Err(Error::RevealNotStarted);
+            }
+            let caller = self.env().caller();
+
+            let commitment = self
+                .bidder_commitments
+                .get((self.round_id, caller))
+                .ok_or(Error::CommitmentMismatch)?;
+
+            let mut computed = [0u8; 32];
+            self.env()
+                .hash_encoded::<Keccak256, _>(&(&url, bid_amount, salt), &mut computed);
+
+            if computed != commitment {
+                return Err(Error::CommitmentMismatch);
+            }
+
+            self.bidder_proposals.insert((self.round_id, caller), &url);
+            self.bid_amounts.insert((self.round_id, caller), &bid_amount);
+
+            let caller_index = self.bidders.iter().position(|b| *b == caller);
+
+            let is_new_lowest = match self.lowest_bid.get(self.round_id) {
+                Some((holder, current)) => {
+                    bid_amount < current
+                        || (bid_amount == current
+                            && caller_index < self.bidders.iter().position(|b| *b == holder))
+                }
+                None => true,
+            };
+            if is_new_lowest {
+                self.lowest_bid.insert(self.round_id, &(caller, bid_amount));
+            }
+
+            let is_new_highest = match self.highest_score.get(self.round_id) {
+                Some((holder, current)) => {
+                    bid_amount > current
+                        || (bid_amount == current
+                            && caller_index < self.bidders.iter().position(|b| *b == holder))
+                }
+                None => true,
+            };
+            if is_new_highest {
+                self.highest_score.insert(self.round_id, &(caller, bid_amount));
+            }
 
             self.env().emit_event(ProposalSubmitted {
                 bidder: caller,
@@ -115,26 +330,142 @@ mod tendersecure {
             Ok(())
         }
 
+        #[ink(message)]
+        pub fn start_reveal(&mut self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::CallerNotOwner);
+            }
+            self.submit_proposal_phase_started = false;
+            self.reveal_phase_started = true;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn stop_reveal(&mut self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::CallerNotOwner);
+            }
+            self.reveal_phase_started = false;
+
+            Ok(())
+        }
+
+        /// Settles the round by paying the escrowed tender amount to the
+        /// owner's chosen `winner_id`. A manual override of the automated
+        /// [`Self::select_lowest_bidder`] / [`Self::select_highest_score`].
         #[ink(message)]
         pub fn pick_bidder(&mut self, winner_id: AccountId) -> Result<()> {
             if self.bidders.len() == 0 {
                 return Err(Error::NoEntries);
             }
+            if self.reveal_phase_started {
+                return Err(Error::RevealPhaseOpen);
+            }
+            if self.env().caller() != self.owner {
+                return Err(Error::CallerNotOwner);
+            }
+
+            self.settle(winner_id, SelectionCriterion::Manual)
+        }
+
+        /// Automatically settles the round in favor of whoever revealed the
+        /// lowest `bid_amount`, breaking ties in favor of the earliest submission.
+        #[ink(message)]
+        pub fn select_lowest_bidder(&mut self) -> Result<()> {
+            if self.reveal_phase_started {
+                return Err(Error::RevealPhaseOpen);
+            }
+            let (winner, _) = self.lowest_bid.get(self.round_id).ok_or(Error::NoEntries)?;
+            self.settle(winner, SelectionCriterion::LowestBid)
+        }
+
+        /// Automatically settles the round in favor of whoever revealed the
+        /// highest `bid_amount` (score), breaking ties in favor of the earliest submission.
+        #[ink(message)]
+        pub fn select_highest_score(&mut self) -> Result<()> {
+            if self.reveal_phase_started {
+                return Err(Error::RevealPhaseOpen);
+            }
+            let (winner, _) = self.highest_score.get(self.round_id).ok_or(Error::NoEntries)?;
+            self.settle(winner, SelectionCriterion::HighestScore)
+        }
 
-            let winner = winner_id;
-            let amount: Balance = self.env().balance();
+        /// Pays the escrowed tender amount to `winner` and closes the round.
+        fn settle(&mut self, winner: AccountId, criterion: SelectionCriterion) -> Result<()> {
+            if self.round_closed {
+                return Err(Error::RoundAlreadyClosed);
+            }
+
+            let amount = self.tender_amount;
 
             if self.env().transfer(winner, amount).is_err() {
                 return Err(Error::ErrorTransferringAmount);
             }
 
-            for bidder in self.bidders.iter() {
-                self.bidder_proposals.remove(bidder);
+            self.tender_amount = 0;
+            self.winners.insert(self.round_id, &winner);
+            self.round_closed = true;
+
+            self.env().emit_event(Won {
+                winner,
+                amount,
+                criterion,
+            });
+
+            Ok(())
+        }
+
+        /// Cancels the currently open round: the escrowed tender amount is
+        /// returned to the owner and every bidder's deposit becomes
+        /// withdrawable, with no winner recorded.
+        #[ink(message)]
+        pub fn cancel_round(&mut self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::CallerNotOwner);
+            }
+            if self.round_closed {
+                return Err(Error::RoundAlreadyClosed);
             }
 
-            self.bidders = Vec::new();
+            let tender_amount = self.tender_amount;
+            if tender_amount > 0 {
+                if self.env().transfer(self.owner, tender_amount).is_err() {
+                    return Err(Error::ErrorTransferringAmount);
+                }
+                self.tender_amount = 0;
+            }
+
+            self.round_closed = true;
+            self.submit_proposal_phase_started = false;
+            self.reveal_phase_started = false;
 
-            self.env().emit_event(Won { winner, amount });
+            self.env().emit_event(RoundCancelled {
+                round_id: self.round_id,
+            });
+
+            Ok(())
+        }
+
+        /// Reclaims the caller's bid deposit from a closed round.
+        #[ink(message)]
+        pub fn withdraw(&mut self) -> Result<()> {
+            if !self.round_closed {
+                return Err(Error::RoundStillOpen);
+            }
+
+            let caller = self.env().caller();
+
+            let deposit = self
+                .deposits
+                .get((self.round_id, caller))
+                .ok_or(Error::NothingToWithdraw)?;
+
+            self.deposits.remove((self.round_id, caller));
+
+            if self.env().transfer(caller, deposit).is_err() {
+                return Err(Error::ErrorTransferringAmount);
+            }
 
             Ok(())
         }
@@ -144,6 +475,13 @@ mod tendersecure {
             if self.env().caller() != self.owner {
                 return Err(Error::CallerNotOwner);
             }
+
+            if self.round_closed {
+                self.round_id = self.round_id.wrapping_add(1);
+                self.round_closed = false;
+                self.bidders = Vec::new();
+            }
+
             self.submit_proposal_phase_started = true;
 
             Ok(())
@@ -165,7 +503,186 @@ mod tendersecure {
     /// The below code is technically just normal Rust code.
     #[cfg(test)]
     mod tests {
+        use super::*;
+        use ink::env::test;
+
+        fn set_caller(caller: AccountId) {
+            test::set_caller::<ink::env::DefaultEnvironment>(caller);
+        }
+
+        fn set_value_transferred(value: Balance) {
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(value);
+        }
+
+        fn commitment_for(url: &str, bid_amount: Balance, salt: [u8; 32]) -> [u8; 32] {
+            let mut commitment = [0u8; 32];
+            ink::env::hash_encoded::<Keccak256, _>(
+                &(&String::from(url), bid_amount, salt),
+                &mut commitment,
+            );
+            commitment
+        }
+
+        /// Runs a round to completion with a single bidder, settling via
+        /// [`Tendersecure::pick_bidder`], and returns the contract plus that
+        /// bidder's account so callers can exercise what happens next.
+        fn settled_round_with_one_bidder() -> (Tendersecure, AccountId) {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut contract = Tendersecure::new();
+            contract.start_bidding_for_tender().unwrap();
+
+            set_caller(accounts.bob);
+            set_value_transferred(50);
+            let salt = [1u8; 32];
+            contract
+                .commit(commitment_for("proposal", 10, salt))
+                .unwrap();
+
+            set_caller(accounts.alice);
+            contract.stop_bidding_for_tender().unwrap();
+            contract.start_reveal().unwrap();
+
+            set_caller(accounts.bob);
+            contract
+                .reveal(String::from("proposal"), 10, salt)
+                .unwrap();
+
+            set_caller(accounts.alice);
+            contract.stop_reveal().unwrap();
+            contract.pick_bidder(accounts.bob).unwrap();
+
+            (contract, accounts.bob)
+        }
+
+        #[ink::test]
+        fn withdraw_after_settle_returns_deposit_once() {
+            let (mut contract, bob) = settled_round_with_one_bidder();
 
+            set_caller(bob);
+            assert_eq!(contract.withdraw(), Ok(()));
+            assert_eq!(contract.withdraw(), Err(Error::NothingToWithdraw));
+        }
+
+        #[ink::test]
+        fn settling_an_already_closed_round_is_rejected() {
+            let (mut contract, bob) = settled_round_with_one_bidder();
+
+            assert_eq!(
+                contract.pick_bidder(bob),
+                Err(Error::RoundAlreadyClosed)
+            );
+            assert_eq!(
+                contract.select_lowest_bidder(),
+                Err(Error::RoundAlreadyClosed)
+            );
+            assert_eq!(contract.cancel_round(), Err(Error::RoundAlreadyClosed));
+        }
+
+        #[ink::test]
+        fn cancel_round_refunds_tender_amount_and_bid_deposits() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut contract = Tendersecure::new();
+            contract.start_bidding_for_tender().unwrap();
+
+            set_value_transferred(500);
+            contract.submit_tender_amount().unwrap();
+
+            set_caller(accounts.bob);
+            set_value_transferred(50);
+            contract
+                .commit(commitment_for("proposal", 10, [2u8; 32]))
+                .unwrap();
+
+            set_caller(accounts.alice);
+            assert_eq!(contract.cancel_round(), Ok(()));
+            assert_eq!(contract.get_tender_amount(), 0);
+
+            set_caller(accounts.bob);
+            assert_eq!(contract.withdraw(), Ok(()));
+        }
+
+        #[ink::test]
+        fn reveal_with_mismatched_salt_is_rejected() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut contract = Tendersecure::new();
+            contract.start_bidding_for_tender().unwrap();
+
+            set_caller(accounts.bob);
+            set_value_transferred(50);
+            contract
+                .commit(commitment_for("proposal", 10, [3u8; 32]))
+                .unwrap();
+
+            set_caller(accounts.alice);
+            contract.start_reveal().unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                contract.reveal(String::from("proposal"), 10, [4u8; 32]),
+                Err(Error::CommitmentMismatch)
+            );
+        }
+
+        #[ink::test]
+        fn cancelling_mid_reveal_does_not_leak_reveal_phase_into_next_round() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut contract = Tendersecure::new();
+            contract.start_bidding_for_tender().unwrap();
+            contract.stop_bidding_for_tender().unwrap();
+            contract.start_reveal().unwrap();
+
+            assert_eq!(contract.cancel_round(), Ok(()));
+
+            contract.start_bidding_for_tender().unwrap();
+            assert!(!contract.can_reveal());
+
+            set_caller(accounts.bob);
+            set_value_transferred(50);
+            assert_eq!(
+                contract.commit(commitment_for("proposal", 10, [8u8; 32])),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn commit_after_reveal_started_is_rejected() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut contract = Tendersecure::new();
+            contract.start_bidding_for_tender().unwrap();
+            contract.start_reveal().unwrap();
+
+            set_caller(accounts.bob);
+            set_value_transferred(50);
+            assert_eq!(
+                contract.commit(commitment_for("proposal", 10, [7u8; 32])),
+                Err(Error::BiddingNotStarted)
+            );
+        }
+
+        #[ink::test]
+        fn committing_twice_in_the_same_round_is_rejected() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            set_caller(accounts.alice);
+            let mut contract = Tendersecure::new();
+            contract.start_bidding_for_tender().unwrap();
+
+            set_caller(accounts.bob);
+            set_value_transferred(50);
+            contract
+                .commit(commitment_for("proposal", 10, [5u8; 32]))
+                .unwrap();
+
+            set_value_transferred(50);
+            assert_eq!(
+                contract.commit(commitment_for("proposal", 10, [6u8; 32])),
+                Err(Error::BidderAlreadySubmittedProposal)
+            );
+        }
     }
 
 